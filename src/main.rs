@@ -1,5 +1,10 @@
-use bevy::{prelude::*, sprite::collide_aabb::collide, time::FixedTimestep};
+use bevy::{
+    prelude::*,
+    sprite::collide_aabb::{collide, Collision},
+    time::FixedTimestep,
+};
 use rand::Rng;
+use std::{collections::HashMap, time::Duration};
 
 // Defines the amount of time that should elapse between each physics step.
 const TIME_STEP: f32 = 1.0 / 60.0;
@@ -9,8 +14,6 @@ const TIME_STEP: f32 = 1.0 / 60.0;
 const PACMAN_SIZE: Vec3 = Vec3::new(60.0, 60.0, 0.0);
 const GAP_BETWEEN_PACMAN_AND_FLOOR: f32 = 60.0;
 const PACMAN_SPEED: f32 = 500.0;
-// How close can the pacman get to the wall
-const PACMAN_PADDING: f32 = 10.0;
 
 const WALL_THICKNESS: f32 = 10.0;
 // x coordinates
@@ -23,58 +26,233 @@ const TOP_WALL: f32 = 300.;
 const SCOREBOARD_FONT_SIZE: f32 = 40.0;
 const SCOREBOARD_TEXT_PADDING: Val = Val::Px(5.0);
 
+const STARTING_LIVES: u32 = 3;
+
 const BACKGROUND_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
 const PACMAN_COLOR: Color = Color::rgb(0.3, 0.3, 0.7);
 const WALL_COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
 const TEXT_COLOR: Color = Color::rgb(0.5, 0.5, 1.0);
 const SCORE_COLOR: Color = Color::rgb(1.0, 0.5, 0.5);
+const MENU_TEXT_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
 
 const ENEMY_SPAWN_STEP: f64 = 1.0; //seconds
 const ENEMY_COLOR: Color = Color::rgb(1.0, 1.0, 0.5);
 const ENEMY_SIZE: Vec2 = Vec2::new(30.0, 30.0);
+const ENEMY_SPEED: f32 = 150.0;
+// Fraction of spawned enemies that are dangerous rather than edible
+const HOSTILE_ENEMY_CHANCE: f64 = 0.7;
+// How long ghosts spend chasing Pacman vs. retreating to their corner
+const CHASE_DURATION: f32 = 20.0;
+const SCATTER_DURATION: f32 = 7.0;
+// The arena corner each ghost retreats to while scattering
+const SCATTER_CORNERS: [Vec2; 4] = [
+    Vec2::new(LEFT_WALL, TOP_WALL),
+    Vec2::new(RIGHT_WALL, TOP_WALL),
+    Vec2::new(LEFT_WALL, BOTTOM_WALL),
+    Vec2::new(RIGHT_WALL, BOTTOM_WALL),
+];
+
+const PELLET_SIZE: Vec2 = Vec2::new(10.0, 10.0);
+const GAP_BETWEEN_PELLETS: f32 = 30.0;
+// How far the pellet grid stays clear of the arena walls
+const PELLET_EDGE_MARGIN: f32 = 60.0;
+const PELLET_COLOR: Color = Color::rgb(1.0, 0.84, 0.0);
+
+// Which movement style Pacman uses. Swap this to try the side-scrolling
+// platformer controls; the default keeps the original top-down free movement.
+const MOVEMENT_MODE: MovementMode = MovementMode::TopDown;
+const GRAVITY: Vec2 = Vec2::new(0.0, -1200.0);
+const JUMP_VELOCITY: f32 = 500.0;
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .insert_resource(Scoreboard { score: 0 })
+        .insert_resource(Lives(STARTING_LIVES))
         .insert_resource(ClearColor(BACKGROUND_COLOR))
+        .insert_resource(GhostModeState {
+            mode: GhostMode::Chase,
+            timer: Timer::from_seconds(CHASE_DURATION, TimerMode::Once),
+        })
+        .insert_resource(Gravity(GRAVITY))
+        .add_state(AppState::Menu)
         .add_startup_system(setup)
-        .add_event::<CollisionEvent>()
+        .add_event::<GameAudioEvent>()
+        // Menu screen
+        .add_system_set(SystemSet::on_enter(AppState::Menu).with_system(menu_setup))
+        .add_system_set(SystemSet::on_update(AppState::Menu).with_system(menu_input))
+        .add_system_set(SystemSet::on_exit(AppState::Menu).with_system(despawn_screen::<OnMenuScreen>))
+        // Playing screen
+        .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(playing_setup))
         .add_system_set(
-            SystemSet::new()
+            SystemSet::on_update(AppState::Playing)
                 .with_run_criteria(FixedTimestep::step(TIME_STEP as f64))
                 .with_system(check_for_collisions)
-                .with_system(move_pacman.before(check_for_collisions))
+                .with_system(move_pacman.before(apply_velocity))
+                .with_system(gravity.after(move_pacman).before(apply_velocity))
+                .with_system(flip_ghost_mode.before(ghost_ai))
+                .with_system(ghost_ai.before(apply_velocity))
                 .with_system(apply_velocity.before(check_for_collisions))
-                .with_system(play_collision_sound.after(check_for_collisions)),
+                .with_system(play_game_audio.after(check_for_collisions))
+                .with_system(check_win_condition.after(check_for_collisions)),
         )
         .add_system_set(
-            SystemSet::new()
+            SystemSet::on_update(AppState::Playing)
                 .with_run_criteria(FixedTimestep::step(ENEMY_SPAWN_STEP as f64))
                 .with_system(spawn_enemy),
         )
+        // Game over screen
+        .add_system_set(SystemSet::on_enter(AppState::GameOver).with_system(game_over_setup))
+        .add_system_set(SystemSet::on_update(AppState::GameOver).with_system(game_over_input))
+        .add_system_set(
+            SystemSet::on_exit(AppState::GameOver).with_system(despawn_screen::<OnGameOverScreen>),
+        )
+        // Victory screen
+        .add_system_set(SystemSet::on_enter(AppState::Victory).with_system(victory_setup))
+        .add_system_set(SystemSet::on_update(AppState::Victory).with_system(victory_input))
+        .add_system_set(
+            SystemSet::on_exit(AppState::Victory).with_system(despawn_screen::<OnVictoryScreen>),
+        )
         .add_system(update_scoreboard)
         .add_system(bevy::window::close_on_esc)
         .run();
 }
 
+/// The top-level flow of the game: a menu the player starts from, the
+/// actual gameplay, and a game-over or victory screen once the run ends.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum AppState {
+    Menu,
+    Playing,
+    GameOver,
+    Victory,
+}
+
 #[derive(Component)]
 struct Pacman;
 
+/// Which control scheme `move_pacman` uses.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum MovementMode {
+    /// The original four-directional free movement.
+    TopDown,
+    /// Side-scrolling platformer movement: left/right plus gravity and jumping.
+    Platformer,
+}
+
+// Downward acceleration applied to Pacman each frame in `Platformer` mode
+#[derive(Resource)]
+struct Gravity(Vec2);
+
+// Jump state, only meaningful in `Platformer` mode
+#[derive(Component)]
+struct Player {
+    on_floor: bool,
+    double_jump: bool,
+}
+
 #[derive(Component, Deref, DerefMut)]
 struct Velocity(Vec2);
 
+// Which directions Pacman is currently blocked from moving in, as of the
+// last `check_for_collisions` run. `move_pacman` consults this so that
+// holding a key into a wall keeps velocity at zero instead of
+// reassigning it from raw input every tick and tunneling through.
+#[derive(Component, Default)]
+struct WallContact {
+    blocked_left: bool,
+    blocked_right: bool,
+    blocked_up: bool,
+    blocked_down: bool,
+}
+
 #[derive(Component)]
 struct Collider;
 
-#[derive(Default)]
-struct CollisionEvent;
+// Whether this enemy overlapped a wall as of `check_for_collisions`'s last
+// run. `ghost_ai` reads this instead of re-scanning every wall itself.
+#[derive(Component, Default)]
+struct TouchingWall(bool);
+
+// Which sound effect a `GameAudioEvent` should play
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+enum AudioKind {
+    PelletEat,
+    EnemyEat,
+    WallBump,
+    LifeLost,
+    LevelClear,
+}
+
+impl AudioKind {
+    // How loud this sound effect plays relative to the others
+    fn volume(self) -> f32 {
+        match self {
+            AudioKind::PelletEat => 0.6,
+            AudioKind::EnemyEat => 0.8,
+            AudioKind::WallBump => 0.4,
+            AudioKind::LifeLost => 1.0,
+            AudioKind::LevelClear => 1.0,
+        }
+    }
+}
+
+// Sent whenever gameplay should react with a sound effect
+struct GameAudioEvent {
+    kind: AudioKind,
+}
 
 #[derive(Component)]
 struct Enemy;
 
+#[derive(Component)]
+struct Pellet;
+
+// Whether a ghost is hunting Pacman or retreating to its scatter corner
+#[derive(Component, Clone, Copy, PartialEq)]
+enum GhostMode {
+    Chase,
+    Scatter,
+}
+
+// The fixed arena corner this ghost retreats to while scattering
+#[derive(Component)]
+struct GhostCorner(Vec2);
+
+// Tracks which `GhostMode` every ghost is currently in and when the
+// next flip between chasing and scattering happens
+#[derive(Resource)]
+struct GhostModeState {
+    mode: GhostMode,
+    timer: Timer,
+}
+
+// Whether touching this enemy costs Pacman a life or scores points
+#[derive(Component, Clone, Copy)]
+enum EnemyKind {
+    Hostile,
+    Edible,
+}
+
+// Marks entities that belong to the menu screen so they can be despawned on exit
+#[derive(Component)]
+struct OnMenuScreen;
+
+// Marks entities that belong to the game-over screen so they can be despawned on exit
+#[derive(Component)]
+struct OnGameOverScreen;
+
+// Marks entities that belong to the victory screen so they can be despawned on exit
+#[derive(Component)]
+struct OnVictoryScreen;
+
+// Preloaded audio clips, one per `AudioKind`
 #[derive(Resource)]
-struct CollisionSound(Handle<AudioSource>);
+struct AudioAssets(HashMap<AudioKind, Handle<AudioSource>>);
+
+// How many more hits Pacman can take before the game is over
+#[derive(Resource)]
+struct Lives(u32);
 
 // This bundle is a collection of the components that define a "wall" in our game
 #[derive(Bundle)]
@@ -160,8 +338,14 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn(Camera2dBundle::default());
 
     // Sound
-    let pacman_collision_sound = asset_server.load("sounds/breakout_collision.ogg");
-    commands.insert_resource(CollisionSound(pacman_collision_sound));
+    let audio_assets = HashMap::from([
+        (AudioKind::PelletEat, asset_server.load("sounds/chomp.ogg")),
+        (AudioKind::EnemyEat, asset_server.load("sounds/enemy_eat.ogg")),
+        (AudioKind::WallBump, asset_server.load("sounds/wall_bump.ogg")),
+        (AudioKind::LifeLost, asset_server.load("sounds/life_lost.ogg")),
+        (AudioKind::LevelClear, asset_server.load("sounds/level_clear.ogg")),
+    ]);
+    commands.insert_resource(AudioAssets(audio_assets));
 
     // Pacman
     let pacman_y = BOTTOM_WALL + GAP_BETWEEN_PACMAN_AND_FLOOR;
@@ -180,6 +364,12 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         Pacman,
+        Velocity(Vec2::ZERO),
+        Player {
+            on_floor: false,
+            double_jump: true,
+        },
+        WallContact::default(),
         Collider,
     ));
 
@@ -218,7 +408,265 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn(WallBundle::new(WallLocation::Top));
 }
 
-fn spawn_enemy(mut commands: Commands) {
+// Draws the title screen the player sees before a run starts
+fn menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_sections([
+            TextSection::new(
+                "PAC-HUMAN\n",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 60.0,
+                    color: MENU_TEXT_COLOR,
+                },
+            ),
+            TextSection::new(
+                "Press Enter to start",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                    font_size: SCOREBOARD_FONT_SIZE,
+                    color: MENU_TEXT_COLOR,
+                },
+            ),
+        ])
+        .with_text_alignment(TextAlignment::CENTER)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Percent(40.0),
+                left: Val::Percent(30.0),
+                ..default()
+            },
+            ..default()
+        }),
+        OnMenuScreen,
+    ));
+}
+
+fn menu_input(keyboard_input: Res<Input<KeyCode>>, mut app_state: ResMut<State<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        app_state.set(AppState::Playing).unwrap();
+    }
+}
+
+// Resets everything needed for a fresh run, so the game is replayable
+// from the menu without restarting the binary
+fn playing_setup(
+    mut commands: Commands,
+    mut scoreboard: ResMut<Scoreboard>,
+    mut lives: ResMut<Lives>,
+    enemy_query: Query<Entity, With<Enemy>>,
+    pellet_query: Query<Entity, With<Pellet>>,
+    mut pacman_query: Query<(&mut Transform, &mut Velocity, &mut Player, &mut WallContact), With<Pacman>>,
+) {
+    scoreboard.score = 0;
+    lives.0 = STARTING_LIVES;
+
+    for enemy_entity in &enemy_query {
+        commands.entity(enemy_entity).despawn();
+    }
+
+    for pellet_entity in &pellet_query {
+        commands.entity(pellet_entity).despawn();
+    }
+    spawn_pellet_grid(&mut commands);
+
+    let (mut pacman_transform, mut pacman_velocity, mut player, mut wall_contact) =
+        pacman_query.single_mut();
+    pacman_transform.translation.x = 0.0;
+    pacman_transform.translation.y = BOTTOM_WALL + GAP_BETWEEN_PACMAN_AND_FLOOR;
+    pacman_velocity.0 = Vec2::ZERO;
+    player.on_floor = false;
+    player.double_jump = true;
+    *wall_contact = WallContact::default();
+}
+
+// Draws the game-over screen once Pacman runs out of lives
+fn game_over_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    scoreboard: Res<Scoreboard>,
+) {
+    commands.spawn((
+        TextBundle::from_sections([
+            TextSection::new(
+                "GAME OVER\n",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 60.0,
+                    color: MENU_TEXT_COLOR,
+                },
+            ),
+            TextSection::new(
+                format!("Final score: {}\n", scoreboard.score),
+                TextStyle {
+                    font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                    font_size: SCOREBOARD_FONT_SIZE,
+                    color: MENU_TEXT_COLOR,
+                },
+            ),
+            TextSection::new(
+                "Press Enter to return to the menu",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                    font_size: SCOREBOARD_FONT_SIZE,
+                    color: MENU_TEXT_COLOR,
+                },
+            ),
+        ])
+        .with_text_alignment(TextAlignment::CENTER)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Percent(35.0),
+                left: Val::Percent(25.0),
+                ..default()
+            },
+            ..default()
+        }),
+        OnGameOverScreen,
+    ));
+}
+
+fn game_over_input(keyboard_input: Res<Input<KeyCode>>, mut app_state: ResMut<State<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        app_state.set(AppState::Menu).unwrap();
+    }
+}
+
+// Draws the victory screen once every pellet has been eaten
+fn victory_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    scoreboard: Res<Scoreboard>,
+) {
+    commands.spawn((
+        TextBundle::from_sections([
+            TextSection::new(
+                "YOU WIN\n",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 60.0,
+                    color: MENU_TEXT_COLOR,
+                },
+            ),
+            TextSection::new(
+                format!("Final score: {}\n", scoreboard.score),
+                TextStyle {
+                    font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                    font_size: SCOREBOARD_FONT_SIZE,
+                    color: MENU_TEXT_COLOR,
+                },
+            ),
+            TextSection::new(
+                "Press Enter to return to the menu",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                    font_size: SCOREBOARD_FONT_SIZE,
+                    color: MENU_TEXT_COLOR,
+                },
+            ),
+        ])
+        .with_text_alignment(TextAlignment::CENTER)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Percent(35.0),
+                left: Val::Percent(25.0),
+                ..default()
+            },
+            ..default()
+        }),
+        OnVictoryScreen,
+    ));
+}
+
+fn victory_input(keyboard_input: Res<Input<KeyCode>>, mut app_state: ResMut<State<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        app_state.set(AppState::Menu).unwrap();
+    }
+}
+
+// The maze is cleared once the last pellet is eaten
+fn check_win_condition(
+    pellet_query: Query<(), With<Pellet>>,
+    mut app_state: ResMut<State<AppState>>,
+    mut audio_events: EventWriter<GameAudioEvent>,
+) {
+    if pellet_query.is_empty() {
+        audio_events.send(GameAudioEvent {
+            kind: AudioKind::LevelClear,
+        });
+        app_state.set(AppState::Victory).unwrap();
+    }
+}
+
+// Generic cleanup system: despawns every entity tagged with the given
+// screen marker component, used on the `on_exit` of menu-like states
+fn despawn_screen<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Computes how many pellet columns/rows fit inside the arena (minus the
+// edge margin) and spawns a centered grid of `Pellet` colliders, the same
+// way the Breakout example lays out its brick rows and columns.
+fn spawn_pellet_grid(commands: &mut Commands) {
+    let available_width = (RIGHT_WALL - LEFT_WALL) - 2.0 * PELLET_EDGE_MARGIN;
+    let available_height = (TOP_WALL - BOTTOM_WALL) - 2.0 * PELLET_EDGE_MARGIN;
+
+    let n_columns = (available_width / (PELLET_SIZE.x + GAP_BETWEEN_PELLETS)).floor() as i32;
+    let n_rows = (available_height / (PELLET_SIZE.y + GAP_BETWEEN_PELLETS)).floor() as i32;
+
+    let grid_width = n_columns as f32 * (PELLET_SIZE.x + GAP_BETWEEN_PELLETS) - GAP_BETWEEN_PELLETS;
+    let grid_height = n_rows as f32 * (PELLET_SIZE.y + GAP_BETWEEN_PELLETS) - GAP_BETWEEN_PELLETS;
+
+    let left_edge = -grid_width / 2.0 + PELLET_SIZE.x / 2.0;
+    let bottom_edge = -grid_height / 2.0 + PELLET_SIZE.y / 2.0;
+
+    for row in 0..n_rows {
+        for column in 0..n_columns {
+            let pellet_position = Vec2::new(
+                left_edge + column as f32 * (PELLET_SIZE.x + GAP_BETWEEN_PELLETS),
+                bottom_edge + row as f32 * (PELLET_SIZE.y + GAP_BETWEEN_PELLETS),
+            );
+
+            commands.spawn((
+                SpriteBundle {
+                    transform: Transform {
+                        translation: pellet_position.extend(0.0),
+                        scale: PELLET_SIZE.extend(1.0),
+                        ..default()
+                    },
+                    sprite: Sprite {
+                        color: PELLET_COLOR,
+                        ..default()
+                    },
+                    ..default()
+                },
+                Pellet,
+                Collider,
+            ));
+        }
+    }
+}
+
+fn spawn_enemy(mut commands: Commands, ghost_mode_state: Res<GhostModeState>) {
+    let kind = if rand::thread_rng().gen_bool(HOSTILE_ENEMY_CHANCE) {
+        EnemyKind::Hostile
+    } else {
+        EnemyKind::Edible
+    };
+
+    // Send the enemy off in a random direction; `ghost_ai` steers it for
+    // real once the next frame runs, and it ricochets off the arena walls
+    // once `check_for_collisions` sees it hit one.
+    let heading = rand::thread_rng().gen_range(0.0..std::f32::consts::TAU);
+    let velocity = Vec2::new(heading.cos(), heading.sin()) * ENEMY_SPEED;
+
+    let corner = SCATTER_CORNERS[rand::thread_rng().gen_range(0..SCATTER_CORNERS.len())];
+
     commands.spawn((
         SpriteBundle {
             sprite: Sprite {
@@ -237,15 +685,81 @@ fn spawn_enemy(mut commands: Commands) {
             ..default()
         },
         Enemy,
+        kind,
+        Velocity(velocity),
+        ghost_mode_state.mode,
+        GhostCorner(corner),
+        TouchingWall::default(),
         Collider,
     ));
 }
 
+// Flips every ghost between chasing Pacman and scattering to its corner
+// on a timer, so pursuit ebbs and flows like the original game.
+fn flip_ghost_mode(time: Res<Time>, mut ghost_mode_state: ResMut<GhostModeState>) {
+    ghost_mode_state.timer.tick(time.delta());
+    if ghost_mode_state.timer.just_finished() {
+        ghost_mode_state.mode = match ghost_mode_state.mode {
+            GhostMode::Chase => GhostMode::Scatter,
+            GhostMode::Scatter => GhostMode::Chase,
+        };
+        let next_duration = match ghost_mode_state.mode {
+            GhostMode::Chase => CHASE_DURATION,
+            GhostMode::Scatter => SCATTER_DURATION,
+        };
+        ghost_mode_state
+            .timer
+            .set_duration(Duration::from_secs_f32(next_duration));
+        ghost_mode_state.timer.reset();
+    }
+}
+
+// Steers every ghost toward Pacman while chasing, or toward its assigned
+// corner while scattering.
+fn ghost_ai(
+    ghost_mode_state: Res<GhostModeState>,
+    pacman_query: Query<&Transform, With<Pacman>>,
+    mut enemy_query: Query<
+        (&Transform, &mut Velocity, &mut GhostMode, &GhostCorner, &TouchingWall),
+        With<Enemy>,
+    >,
+) {
+    let pacman_transform = pacman_query.single();
+
+    for (enemy_transform, mut enemy_velocity, mut ghost_mode, corner, touching_wall) in
+        &mut enemy_query
+    {
+        *ghost_mode = ghost_mode_state.mode;
+
+        // Still clearing a wall this tick? Leave `check_for_collisions`'s
+        // bounce velocity alone instead of clobbering it with a fresh
+        // chase/scatter heading, or the reflection never gets a chance to
+        // carry the ghost off the wall. `touching_wall` was already
+        // computed by `check_for_collisions` on the previous tick, so
+        // there's no need to re-scan every wall here too.
+        if touching_wall.0 {
+            continue;
+        }
+
+        let target = match *ghost_mode {
+            GhostMode::Chase => pacman_transform.translation.truncate(),
+            GhostMode::Scatter => corner.0,
+        };
+
+        let to_target = target - enemy_transform.translation.truncate();
+        enemy_velocity.0 = if to_target == Vec2::ZERO {
+            Vec2::ZERO
+        } else {
+            to_target.normalize() * ENEMY_SPEED
+        };
+    }
+}
+
 fn move_pacman(
     keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<&mut Transform, With<Pacman>>,
+    mut query: Query<(&mut Velocity, &mut Player, &WallContact), With<Pacman>>,
 ) {
-    let mut pacman_transform = query.single_mut();
+    let (mut pacman_velocity, mut player, wall_contact) = query.single_mut();
 
     let x_direction = if keyboard_input.pressed(KeyCode::Left) {
         -1.0
@@ -254,26 +768,53 @@ fn move_pacman(
     } else {
         0.0
     };
-    let y_direction = if keyboard_input.pressed(KeyCode::Down) {
-        -1.0
-    } else if keyboard_input.pressed(KeyCode::Up) {
-        1.0
-    } else {
+    // Keep the velocity component zeroed while a key keeps pushing into a
+    // wall we're already in contact with, instead of reassigning it from
+    // raw input and burrowing further in before the next collision check.
+    pacman_velocity.x = if (x_direction < 0.0 && wall_contact.blocked_left)
+        || (x_direction > 0.0 && wall_contact.blocked_right)
+    {
         0.0
+    } else {
+        x_direction * PACMAN_SPEED
     };
 
-    let new_pacan_x_position =
-        pacman_transform.translation.x + x_direction * PACMAN_SPEED * TIME_STEP;
-    let new_pacman_y_position =
-        pacman_transform.translation.y + y_direction * PACMAN_SPEED * TIME_STEP;
+    if MOVEMENT_MODE == MovementMode::Platformer {
+        if keyboard_input.just_pressed(KeyCode::Space) {
+            if player.on_floor {
+                pacman_velocity.y = JUMP_VELOCITY;
+                player.on_floor = false;
+            } else if player.double_jump {
+                pacman_velocity.y = JUMP_VELOCITY;
+                player.double_jump = false;
+            }
+        }
+    } else {
+        let y_direction = if keyboard_input.pressed(KeyCode::Down) {
+            -1.0
+        } else if keyboard_input.pressed(KeyCode::Up) {
+            1.0
+        } else {
+            0.0
+        };
+        pacman_velocity.y = if (y_direction < 0.0 && wall_contact.blocked_down)
+            || (y_direction > 0.0 && wall_contact.blocked_up)
+        {
+            0.0
+        } else {
+            y_direction * PACMAN_SPEED
+        };
+    }
+}
 
-    let left_bound = LEFT_WALL + WALL_THICKNESS / 2.0 + PACMAN_SIZE.x / 2.0 + PACMAN_PADDING;
-    let right_bound = RIGHT_WALL - WALL_THICKNESS / 2.0 - PACMAN_SIZE.x / 2.0 - PACMAN_PADDING;
-    let up_bound = TOP_WALL + WALL_THICKNESS / 2.0 + PACMAN_SIZE.y / 2.0 + PACMAN_PADDING;
-    let bottom_bound = BOTTOM_WALL - WALL_THICKNESS / 2.0 - PACMAN_SIZE.y / 2.0 - PACMAN_PADDING;
+// Accumulates downward velocity each step; only takes effect in `Platformer` mode
+fn gravity(gravity: Res<Gravity>, mut query: Query<&mut Velocity, With<Pacman>>) {
+    if MOVEMENT_MODE != MovementMode::Platformer {
+        return;
+    }
 
-    pacman_transform.translation.x = new_pacan_x_position.clamp(left_bound, right_bound);
-    pacman_transform.translation.y = new_pacman_y_position.clamp(bottom_bound, up_bound);
+    let mut pacman_velocity = query.single_mut();
+    pacman_velocity.0 += gravity.0 * TIME_STEP;
 }
 
 fn apply_velocity(mut query: Query<(&mut Transform, &Velocity)>) {
@@ -291,43 +832,175 @@ fn update_scoreboard(scoreboard: Res<Scoreboard>, mut query: Query<&mut Text>) {
 fn check_for_collisions(
     mut commands: Commands,
     mut scoreboard: ResMut<Scoreboard>,
-    pacman_query: Query<&Transform, With<Pacman>>,
-    collider_query: Query<(Entity, &Transform, Option<&Enemy>), With<Collider>>,
-    mut collision_events: EventWriter<CollisionEvent>,
+    mut lives: ResMut<Lives>,
+    mut app_state: ResMut<State<AppState>>,
+    mut pacman_query: Query<(&Transform, &mut Velocity, &mut Player, &mut WallContact), With<Pacman>>,
+    mut enemy_query: Query<
+        (Entity, &Transform, &mut Velocity, &EnemyKind, &mut TouchingWall),
+        (With<Enemy>, Without<Pacman>),
+    >,
+    pellet_query: Query<(Entity, &Transform), With<Pellet>>,
+    wall_query: Query<&Transform, (With<Collider>, Without<Enemy>, Without<Pacman>, Without<Pellet>)>,
+    mut audio_events: EventWriter<GameAudioEvent>,
 ) {
-    let pacman_transform = pacman_query.single();
+    let (pacman_transform, mut pacman_velocity, mut player, mut wall_contact) =
+        pacman_query.single_mut();
     let pacman_size = pacman_transform.scale.truncate();
 
-    // check collision with walls
-    for (collider_entity, transform, maybe_enemy) in &collider_query {
+    // Pacman vs walls: zero only the velocity component pushing into the
+    // wall, so the player slides along it instead of stopping dead.
+    // `wall_contact` is rebuilt from scratch every tick and fed back into
+    // `move_pacman` next tick, so holding a key into the wall can't
+    // reassign velocity from input and burrow through it.
+    let was_touching_wall = wall_contact.blocked_left
+        || wall_contact.blocked_right
+        || wall_contact.blocked_up
+        || wall_contact.blocked_down;
+    *wall_contact = WallContact::default();
+    let mut touching_wall = false;
+    for wall_transform in &wall_query {
+        let collision = collide(
+            pacman_transform.translation,
+            pacman_size,
+            wall_transform.translation,
+            wall_transform.scale.truncate(),
+        );
+        if let Some(collision) = collision {
+            touching_wall = true;
+            match collision {
+                Collision::Left => {
+                    pacman_velocity.x = 0.0;
+                    wall_contact.blocked_right = true;
+                }
+                Collision::Right => {
+                    pacman_velocity.x = 0.0;
+                    wall_contact.blocked_left = true;
+                }
+                Collision::Top => {
+                    // Pacman is above the wall, landing on it from a fall.
+                    pacman_velocity.y = 0.0;
+                    wall_contact.blocked_down = true;
+                    if MOVEMENT_MODE == MovementMode::Platformer {
+                        player.on_floor = true;
+                        player.double_jump = true;
+                    }
+                }
+                Collision::Bottom => {
+                    // Pacman bonked something from below (e.g. a ceiling).
+                    pacman_velocity.y = 0.0;
+                    wall_contact.blocked_up = true;
+                }
+                Collision::Inside => {}
+            }
+        }
+    }
+    if touching_wall && !was_touching_wall {
+        audio_events.send(GameAudioEvent {
+            kind: AudioKind::WallBump,
+        });
+    }
+
+    // Pacman vs enemies: eat edible enemies, lose a life to hostile ones
+    for (enemy_entity, enemy_transform, _, enemy_kind, _) in &enemy_query {
         let collision = collide(
             pacman_transform.translation,
             pacman_size,
-            transform.translation,
-            transform.scale.truncate(),
+            enemy_transform.translation,
+            enemy_transform.scale.truncate(),
         );
         if collision.is_some() {
-            // Sends a collision event so that other systems can react to the collision
-            collision_events.send_default();
+            commands.entity(enemy_entity).despawn();
+
+            match enemy_kind {
+                EnemyKind::Edible => {
+                    audio_events.send(GameAudioEvent {
+                        kind: AudioKind::EnemyEat,
+                    });
+                    scoreboard.score += 1;
+                }
+                EnemyKind::Hostile => {
+                    audio_events.send(GameAudioEvent {
+                        kind: AudioKind::LifeLost,
+                    });
+                    lives.0 = lives.0.saturating_sub(1);
+                    if lives.0 == 0 {
+                        app_state.set(AppState::GameOver).unwrap();
+                    }
+                }
+            }
+        }
+    }
 
-            // Enemy should be despawned and increment the scoreboard on collision
-            if maybe_enemy.is_some() {
-                scoreboard.score += 1;
-                commands.entity(collider_entity).despawn();
+    // Pacman vs pellets: eating one scores a point
+    for (pellet_entity, pellet_transform) in &pellet_query {
+        let collision = collide(
+            pacman_transform.translation,
+            pacman_size,
+            pellet_transform.translation,
+            pellet_transform.scale.truncate(),
+        );
+        if collision.is_some() {
+            audio_events.send(GameAudioEvent {
+                kind: AudioKind::PelletEat,
+            });
+            commands.entity(pellet_entity).despawn();
+            scoreboard.score += 1;
+        }
+    }
+
+    // Enemies vs walls: reflect the velocity component pushing into the
+    // wall so enemies ricochet inside the arena, and cache the touch state
+    // so `ghost_ai` doesn't have to probe the walls again itself.
+    for (_, enemy_transform, mut enemy_velocity, _, mut touching_wall) in &mut enemy_query {
+        let enemy_size = enemy_transform.scale.truncate();
+        touching_wall.0 = false;
+        for wall_transform in &wall_query {
+            let collision = collide(
+                enemy_transform.translation,
+                enemy_size,
+                wall_transform.translation,
+                wall_transform.scale.truncate(),
+            );
+            if let Some(collision) = collision {
+                touching_wall.0 = true;
+                let (reflect_x, reflect_y) = match collision {
+                    Collision::Left => (enemy_velocity.x > 0.0, false),
+                    Collision::Right => (enemy_velocity.x < 0.0, false),
+                    Collision::Top => (false, enemy_velocity.y < 0.0),
+                    Collision::Bottom => (false, enemy_velocity.y > 0.0),
+                    Collision::Inside => (false, false),
+                };
+                if reflect_x {
+                    enemy_velocity.x = -enemy_velocity.x;
+                }
+                if reflect_y {
+                    enemy_velocity.y = -enemy_velocity.y;
+                }
             }
         }
     }
 }
 
-fn play_collision_sound(
-    collision_events: EventReader<CollisionEvent>,
+// Plays one clip per queued event, so overlapping sounds (e.g. a chomp and
+// a bump in the same frame) all play instead of one handle replaying over
+// itself.
+fn play_game_audio(
+    mut audio_events: EventReader<GameAudioEvent>,
     audio: Res<Audio>,
-    sound: Res<CollisionSound>,
+    audio_assets: Res<AudioAssets>,
 ) {
-    // Play a sound once per frame if a collision occurred.
-    if !collision_events.is_empty() {
-        // This prevents events staying active on the next frame.
-        collision_events.clear();
-        audio.play(sound.0.clone());
+    for event in audio_events.iter() {
+        let Some(source) = audio_assets.0.get(&event.kind) else {
+            continue;
+        };
+
+        audio.play_with_settings(
+            source.clone(),
+            PlaybackSettings {
+                repeat: false,
+                volume: event.kind.volume(),
+                speed: 1.0,
+            },
+        );
     }
 }